@@ -1,30 +1,105 @@
-use crate::{error::ContractError, state::get_commission};
+use crate::{
+    error::ContractError,
+    state::{accrue_fee, get_commission, get_rounding_mode, RoundingMode},
+};
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{to_binary, Addr, Coin, Decimal, Deps, DepsMut, StdError, Uint128};
-use cw20::Cw20Coin;
+use cosmwasm_std::{to_binary, Addr, Api, Coin, Decimal, DepsMut, StdError, Uint128};
+use cw20::{Cw20Coin, Cw20CoinVerified};
 use std::convert::TryInto;
 
+/// An amount as deserialized off the wire: a cw20 variant carries an unverified address
+/// string and a native variant has not yet had its denom checked. Call [`AmountUnchecked::validate`]
+/// before acting on one.
 #[cw_serde]
-pub enum Amount {
+pub enum AmountUnchecked {
     Native(Coin),
-    // FIXME? USe Cw20CoinVerified, and validate cw20 addresses
     Cw20(Cw20Coin),
 }
 
+impl AmountUnchecked {
+    /// Parse a `cw20:<address>` or plain native denom, as used on `ChannelState`/ICS20 memos.
+    pub fn from_parts(denom: String, amount: Uint128) -> Result<Self, ContractError> {
+        match denom.strip_prefix("cw20:") {
+            Some(address) if !address.is_empty() => Ok(AmountUnchecked::Cw20(Cw20Coin {
+                address: address.to_string(),
+                amount,
+            })),
+            Some(_) => Err(ContractError::InvalidCw20Denom {}),
+            None => Ok(AmountUnchecked::Native(Coin { denom, amount })),
+        }
+    }
+
+    pub fn cw20(amount: u128, addr: &str) -> Self {
+        AmountUnchecked::Cw20(Cw20Coin {
+            address: addr.into(),
+            amount: Uint128::new(amount),
+        })
+    }
+
+    pub fn native(amount: u128, denom: &str) -> Self {
+        AmountUnchecked::Native(Coin {
+            denom: denom.to_string(),
+            amount: Uint128::new(amount),
+        })
+    }
+
+    /// Validate a cw20 address via `api.addr_validate`, or check a native denom's
+    /// length and charset, turning this into an [`Amount`] that is safe to send to.
+    pub fn validate(&self, api: &dyn Api) -> Result<Amount, ContractError> {
+        match self {
+            AmountUnchecked::Native(c) => {
+                validate_native_denom(&c.denom)?;
+                Ok(Amount::Native(c.clone()))
+            }
+            AmountUnchecked::Cw20(c) => {
+                let address = api.addr_validate(&c.address)?;
+                Ok(Amount::Cw20(Cw20CoinVerified {
+                    address,
+                    amount: c.amount,
+                }))
+            }
+        }
+    }
+}
+
+/// Native denoms follow the same length/charset rule as the SDK's bank module.
+fn validate_native_denom(denom: &str) -> Result<(), ContractError> {
+    if denom.len() < 3 || denom.len() > 128 {
+        return Err(ContractError::InvalidNativeDenom(denom.to_string()));
+    }
+    if !denom
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | ':' | '.' | '_' | '-'))
+    {
+        return Err(ContractError::InvalidNativeDenom(denom.to_string()));
+    }
+    Ok(())
+}
+
+/// A validated amount: a cw20 variant carries a verified `Addr`, a native variant has
+/// an already-validated denom. Every outgoing transfer is built from one of these.
+#[cw_serde]
+pub enum Amount {
+    Native(Coin),
+    Cw20(Cw20CoinVerified),
+}
+
 impl Amount {
-    // TODO: write test for this
+    /// Reconstruct a validated amount from a denom that was previously produced by
+    /// [`Amount::denom`] (e.g. a storage key). The denom is trusted, not re-validated.
     pub fn from_parts(denom: String, amount: Uint128) -> Self {
-        if denom.starts_with("cw20:") {
-            let address = denom.get(5..).unwrap().into();
-            Amount::Cw20(Cw20Coin { address, amount })
-        } else {
-            Amount::Native(Coin { denom, amount })
+        match denom.strip_prefix("cw20:") {
+            Some(address) => Amount::Cw20(Cw20CoinVerified {
+                address: Addr::unchecked(address),
+                amount,
+            }),
+            None => Amount::Native(Coin { denom, amount }),
         }
     }
 
     pub fn cw20(amount: u128, addr: &str) -> Self {
-        Amount::Cw20(Cw20Coin {
-            address: addr.into(),
+        Amount::Cw20(Cw20CoinVerified {
+            address: Addr::unchecked(addr),
             amount: Uint128::new(amount),
         })
     }
@@ -75,7 +150,7 @@ impl Amount {
             })),
             ),
             Amount::Cw20(c) => Ok(Some(cosmwasm_std::CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute {
-                contract_addr: c.address.clone(),
+                contract_addr: c.address.clone().into(),
                 msg: to_binary(&cw20::Cw20ExecuteMsg::Transfer {
                     recipient: addr.into(),
                     amount: c.amount,
@@ -87,21 +162,51 @@ impl Amount {
     }
 }
 
+/// Round a fractional commission amount into a whole-token `Decimal` according to `mode`.
+fn round_commission(value: Decimal, mode: RoundingMode) -> Decimal {
+    match mode {
+        RoundingMode::Ceil => value.ceil(),
+        RoundingMode::Floor => value.floor(),
+        RoundingMode::Round => (value + Decimal::percent(50)).floor(),
+        RoundingMode::Bankers => {
+            let floor = value.floor();
+            let frac = value - floor;
+            let half = Decimal::percent(50);
+            if frac < half {
+                floor
+            } else if frac > half {
+                floor + Decimal::one()
+            } else {
+                let floor_uint: Uint128 = floor * Uint128::one();
+                if floor_uint.u128() % 2 == 0 {
+                    floor
+                } else {
+                    floor + Decimal::one()
+                }
+            }
+        }
+    }
+}
+
 pub fn calculate_lock_in(
-    deps: Deps,
+    deps: DepsMut,
+    channel_id: &str,
     amount: Amount,
 ) -> Result<(Amount, Amount), ContractError>  {
 
     if amount.is_empty() {
         return Err(ContractError::NoFunds {});
     }
-    let comm = get_commission(deps.storage)?;
+    let comm = get_commission(deps.storage, channel_id, &amount.denom())?;
     let amnt_decimal = Decimal::from_ratio(amount.amount(), 1u32);
     let lock_in = comm.checked_mul(amnt_decimal).map_err(|_| StdError::generic_err("error multiplying1"))?;
-    let lock_in = lock_in.ceil();
+    let rounding_mode = get_rounding_mode(deps.storage)?;
+    let lock_in = round_commission(lock_in, rounding_mode);
     let lock_in_uint: Uint128 = lock_in * Uint128::one();
     let transfer_amnt_uint = amount.amount().checked_sub(lock_in_uint).map_err(|_| StdError::generic_err("error subtracting"))?;
-    
+
+    accrue_fee(deps.storage, &amount.denom(), lock_in_uint)?;
+
     let lock_in_amount = match amount.clone() {
         Amount::Native(c) => {
             let coin = Coin {
@@ -113,7 +218,7 @@ pub fn calculate_lock_in(
             )
         }
         Amount::Cw20(coin) => {
-            let coin = Cw20Coin {
+            let coin = Cw20CoinVerified {
                 address: coin.address.clone(),
                 amount: lock_in_uint,
             };
@@ -134,7 +239,7 @@ pub fn calculate_lock_in(
             )
         }
         Amount::Cw20(coin) => {
-            let coin = Cw20Coin {
+            let coin = Cw20CoinVerified {
                 address: coin.address.clone(),
                 amount: transfer_amnt_uint,
             };
@@ -152,7 +257,10 @@ mod tests {
     use cosmwasm_std::QuerierWrapper;
     use cosmwasm_std::{testing::mock_dependencies, WasmMsg};
 
-    use crate::{msg::ExecuteMsg, state::set_commission};
+    use crate::{
+        msg::ExecuteMsg,
+        state::{set_commission, Config, CONFIG},
+    };
     use super::calculate_lock_in;
 
     use super::*;
@@ -212,18 +320,28 @@ mod tests {
     fn test_calculate_lock_in_native() {
         let mut owned = mock_dependencies();
         set_commission(&mut owned.storage, Decimal::percent(10)).unwrap();
+        CONFIG
+            .save(
+                &mut owned.storage,
+                &Config {
+                    default_timeout: 0,
+                    default_gas_limit: None,
+                    total_beneficiary_weight: 0,
+                    rounding_mode: RoundingMode::Ceil,
+                },
+            )
+            .unwrap();
         let deps = DepsMut {
             storage: &mut owned.storage,
             api: &owned.api,
             querier: QuerierWrapper::new(&owned.querier),
         };
-        let reference = deps.as_ref();
 
         let amount = Amount::Native(Coin {
             denom: "uusd".to_string(),
             amount: Uint128::new(100),
         });
-        let lock_in_amount = calculate_lock_in(reference, amount).unwrap();
+        let lock_in_amount = calculate_lock_in(deps, "channel-0", amount).unwrap();
 
         match lock_in_amount {
             (Amount::Native(coin_lock), Amount::Native(coin_transfer)) => {
@@ -240,18 +358,28 @@ mod tests {
     fn test_calculate_lock_in_cw20() {
         let mut owned = mock_dependencies();
         set_commission(&mut owned.storage, Decimal::percent(10)).unwrap();
+        CONFIG
+            .save(
+                &mut owned.storage,
+                &Config {
+                    default_timeout: 0,
+                    default_gas_limit: None,
+                    total_beneficiary_weight: 0,
+                    rounding_mode: RoundingMode::Ceil,
+                },
+            )
+            .unwrap();
         let deps = DepsMut {
             storage: &mut owned.storage,
             api: &owned.api,
             querier: QuerierWrapper::new(&owned.querier),
         };
-        let reference = deps.as_ref();
 
-        let amount = Amount::Cw20(Cw20Coin {
-            address: "contract_address".to_string(),
+        let amount = Amount::Cw20(Cw20CoinVerified {
+            address: Addr::unchecked("contract_address"),
             amount: Uint128::new(100),
         });
-        let lock_in_amount = calculate_lock_in(reference, amount).unwrap();
+        let lock_in_amount = calculate_lock_in(deps, "channel-0", amount).unwrap();
 
         match lock_in_amount {
             (Amount::Cw20(coin_lock_in), Amount::Cw20(coin_transfer)) => {
@@ -264,4 +392,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_amount_unchecked_validate_native_rejects_bad_denom() {
+        let deps = mock_dependencies();
+        let amount = AmountUnchecked::native(100, "a");
+        let err = amount.validate(&deps.api).unwrap_err();
+        assert_eq!(err, ContractError::InvalidNativeDenom("a".to_string()));
+    }
+
+    #[test]
+    fn test_amount_unchecked_validate_cw20() {
+        let deps = mock_dependencies();
+        let amount = AmountUnchecked::cw20(100, "contract_address");
+        let validated = amount.validate(&deps.api).unwrap();
+        match validated {
+            Amount::Cw20(c) => {
+                assert_eq!(c.address, Addr::unchecked("contract_address"));
+                assert_eq!(c.amount, Uint128::new(100));
+            }
+            _ => panic!("Unexpected Amount variant"),
+        }
+    }
+
+    #[test]
+    fn test_amount_unchecked_from_parts_rejects_empty_cw20_address() {
+        let err = AmountUnchecked::from_parts("cw20:".to_string(), Uint128::new(100)).unwrap_err();
+        assert_eq!(err, ContractError::InvalidCw20Denom {});
+    }
+
+    #[test]
+    fn test_round_commission_modes() {
+        // 2.5 straddles a half: Ceil/Round go up, Floor stays down, Bankers rounds to even (2)
+        let value = Decimal::from_ratio(5u32, 2u32);
+        assert_eq!(round_commission(value, RoundingMode::Ceil), Decimal::from_ratio(3u32, 1u32));
+        assert_eq!(round_commission(value, RoundingMode::Floor), Decimal::from_ratio(2u32, 1u32));
+        assert_eq!(round_commission(value, RoundingMode::Round), Decimal::from_ratio(3u32, 1u32));
+        assert_eq!(round_commission(value, RoundingMode::Bankers), Decimal::from_ratio(2u32, 1u32));
+
+        // 3.5 straddles a half the other way: Bankers rounds up to the even integer (4)
+        let value = Decimal::from_ratio(7u32, 2u32);
+        assert_eq!(round_commission(value, RoundingMode::Bankers), Decimal::from_ratio(4u32, 1u32));
+
+        // 2.4 isn't a tie: every mode except Ceil rounds down
+        let value = Decimal::percent(240);
+        assert_eq!(round_commission(value, RoundingMode::Ceil), Decimal::from_ratio(3u32, 1u32));
+        assert_eq!(round_commission(value, RoundingMode::Round), Decimal::from_ratio(2u32, 1u32));
+        assert_eq!(round_commission(value, RoundingMode::Bankers), Decimal::from_ratio(2u32, 1u32));
+    }
 }
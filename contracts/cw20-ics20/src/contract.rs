@@ -0,0 +1,386 @@
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Order, Response, StdResult, Uint128};
+
+use crate::amount::{calculate_lock_in, Amount, AmountUnchecked};
+use crate::error::ContractError;
+use crate::msg::ExecuteMsg;
+use crate::state::{
+    get_beneficiaries, increase_channel_balance, set_beneficiaries, set_channel_commission,
+    set_channel_denom_commission, set_commission, set_transfer_cap, ADMIN, CONFIG, FEES_ACCRUED,
+    TOTAL_BENEFICIARY_WEIGHT,
+};
+use crate::supply::assert_transfer_cap;
+
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Transfer { channel_id, amount } => execute_transfer(deps, channel_id, amount),
+        ExecuteMsg::SetCommission { commission } => {
+            execute_set_commission(deps, info, commission)
+        }
+        ExecuteMsg::SetBeneficiaries { beneficiaries } => {
+            execute_set_beneficiaries(deps, info, beneficiaries)
+        }
+        ExecuteMsg::DistributeFees {} => execute_distribute_fees(deps, info),
+        ExecuteMsg::SetChannelCommission {
+            channel_id,
+            commission,
+        } => execute_set_channel_commission(deps, info, channel_id, commission),
+        ExecuteMsg::SetDenomCommission {
+            channel_id,
+            denom,
+            commission,
+        } => execute_set_denom_commission(deps, info, channel_id, denom, commission),
+        ExecuteMsg::SetTransferCap { denom, cap } => execute_set_transfer_cap(deps, info, denom, cap),
+    }
+}
+
+/// Entry point for a user bridging `amount` out over `channel_id`. The amount is
+/// validated, the commission is carved out and accrued, and what's left is credited
+/// against the channel's outstanding balance.
+pub fn execute_transfer(
+    mut deps: DepsMut,
+    channel_id: String,
+    amount: AmountUnchecked,
+) -> Result<Response, ContractError> {
+    let validated = amount.validate(deps.api)?;
+    let denom = validated.denom();
+
+    let (lock_in, transfer_amount) = calculate_lock_in(deps.branch(), &channel_id, validated)?;
+
+    assert_transfer_cap(
+        deps.as_ref(),
+        &channel_id,
+        &transfer_amount,
+        transfer_amount.amount(),
+    )?;
+    increase_channel_balance(deps.storage, &channel_id, &denom, transfer_amount.amount())?;
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer")
+        .add_attribute("channel_id", channel_id)
+        .add_attribute("denom", denom)
+        .add_attribute("locked_in", lock_in.amount())
+        .add_attribute("transferred", transfer_amount.amount()))
+}
+
+pub fn execute_set_commission(
+    deps: DepsMut,
+    info: MessageInfo,
+    commission: cosmwasm_std::Decimal,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    set_commission(deps.storage, commission)?;
+    Ok(Response::new().add_attribute("action", "set_commission"))
+}
+
+pub fn execute_set_beneficiaries(
+    deps: DepsMut,
+    info: MessageInfo,
+    beneficiaries: Vec<(String, u16)>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    let validated = beneficiaries
+        .into_iter()
+        .map(|(addr, weight)| deps.api.addr_validate(&addr).map(|addr| (addr, weight)))
+        .collect::<StdResult<Vec<_>>>()?;
+    set_beneficiaries(deps.storage, validated)?;
+    Ok(Response::new().add_attribute("action", "set_beneficiaries"))
+}
+
+pub fn execute_set_channel_commission(
+    deps: DepsMut,
+    info: MessageInfo,
+    channel_id: String,
+    commission: cosmwasm_std::Decimal,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    set_channel_commission(deps.storage, &channel_id, commission)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_channel_commission")
+        .add_attribute("channel_id", channel_id))
+}
+
+pub fn execute_set_denom_commission(
+    deps: DepsMut,
+    info: MessageInfo,
+    channel_id: String,
+    denom: String,
+    commission: cosmwasm_std::Decimal,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    set_channel_denom_commission(deps.storage, &channel_id, &denom, commission)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_denom_commission")
+        .add_attribute("channel_id", channel_id)
+        .add_attribute("denom", denom))
+}
+
+pub fn execute_set_transfer_cap(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    cap: cosmwasm_std::Decimal,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    set_transfer_cap(deps.storage, &denom, cap)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_transfer_cap")
+        .add_attribute("denom", denom))
+}
+
+/// Drain `FEES_ACCRUED` per denom and pay every registered beneficiary its weighted
+/// share of what was collected, sending the rounding remainder to the last beneficiary.
+pub fn execute_distribute_fees(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    // `set_beneficiaries` only advances this to `TOTAL_BENEFICIARY_WEIGHT` once the
+    // beneficiaries sum correctly; gate on it rather than inferring "configured" from
+    // `get_beneficiaries` being non-empty.
+    let config = CONFIG.load(deps.storage)?;
+    if config.total_beneficiary_weight != TOTAL_BENEFICIARY_WEIGHT {
+        return Err(ContractError::NoBeneficiaries {});
+    }
+
+    let beneficiaries = get_beneficiaries(deps.storage)?;
+    let last = beneficiaries.len().checked_sub(1).ok_or(ContractError::NoBeneficiaries {})?;
+
+    let accruals = FEES_ACCRUED
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, Uint128)>>>()?;
+
+    let mut messages = vec![];
+    for (denom, accrued) in accruals {
+        if accrued.is_zero() {
+            continue;
+        }
+        let mut paid_out = Uint128::zero();
+        for (idx, (addr, weight)) in beneficiaries.iter().enumerate() {
+            let share = if idx == last {
+                accrued - paid_out
+            } else {
+                let share = accrued.multiply_ratio(*weight as u128, TOTAL_BENEFICIARY_WEIGHT as u128);
+                paid_out += share;
+                share
+            };
+            if let Some(msg) = Amount::from_parts(denom.clone(), share).into_cosmos_msg(addr.clone())? {
+                messages.push(msg);
+            }
+        }
+        FEES_ACCRUED.save(deps.storage, &denom, &Uint128::zero())?;
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "distribute_fees"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_info};
+    use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{Addr, BankMsg, CosmosMsg, OwnedDeps};
+
+    use crate::state::{accrue_fee, Config, RoundingMode, ADMIN, CONFIG};
+
+    fn setup(admin: &str) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        ADMIN
+            .set(deps.as_mut(), Some(Addr::unchecked(admin)))
+            .unwrap();
+        CONFIG
+            .save(
+                &mut deps.storage,
+                &Config {
+                    default_timeout: 0,
+                    default_gas_limit: None,
+                    total_beneficiary_weight: 0,
+                    rounding_mode: RoundingMode::Ceil,
+                },
+            )
+            .unwrap();
+        deps
+    }
+
+    #[test]
+    fn test_execute_distribute_fees_remainder_goes_to_last_submitted_beneficiary() {
+        let mut deps = setup("admin");
+
+        // submitted in "zeta" then "alpha" order, which sorts the opposite way
+        // lexicographically -- the remainder must follow submission order
+        execute_set_beneficiaries(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            vec![("zeta".to_string(), 3333), ("alpha".to_string(), 6667)],
+        )
+        .unwrap();
+
+        accrue_fee(&mut deps.storage, "uusd", Uint128::new(100)).unwrap();
+
+        let res = execute_distribute_fees(deps.as_mut(), mock_info("admin", &[])).unwrap();
+        assert_eq!(res.messages.len(), 2);
+
+        match &res.messages[0].msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                assert_eq!(to_address, "zeta");
+                assert_eq!(amount[0].amount, Uint128::new(33));
+            }
+            _ => panic!("unexpected msg"),
+        }
+        match &res.messages[1].msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                assert_eq!(to_address, "alpha");
+                // 67, not the 66 that 100 * 6667 / 10000 would floor to -- the
+                // rounding dust from zeta's share lands here
+                assert_eq!(amount[0].amount, Uint128::new(67));
+            }
+            _ => panic!("unexpected msg"),
+        }
+    }
+
+    #[test]
+    fn test_execute_distribute_fees_rejects_when_beneficiaries_not_configured() {
+        let mut deps = setup("admin");
+        accrue_fee(&mut deps.storage, "uusd", Uint128::new(100)).unwrap();
+
+        let err = execute_distribute_fees(deps.as_mut(), mock_info("admin", &[])).unwrap_err();
+        assert_eq!(err, ContractError::NoBeneficiaries {});
+    }
+
+    #[test]
+    fn test_execute_transfer_validates_amount_before_locking_in() {
+        let mut deps = setup("admin");
+        crate::state::set_commission(&mut deps.storage, cosmwasm_std::Decimal::percent(10)).unwrap();
+
+        // too short to be a real native denom -- validate() must reject it
+        let err = execute_transfer(
+            deps.as_mut(),
+            "channel-0".to_string(),
+            crate::amount::AmountUnchecked::native(100, "a"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidNativeDenom("a".to_string()));
+
+        let res = execute_transfer(
+            deps.as_mut(),
+            "channel-0".to_string(),
+            crate::amount::AmountUnchecked::native(100, "uusd"),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes
+                .iter()
+                .find(|a| a.key == "transferred")
+                .unwrap()
+                .value,
+            "90"
+        );
+    }
+
+    #[test]
+    fn test_execute_transfer_rejects_over_transfer_cap() {
+        let mut deps = setup("admin");
+        crate::state::set_commission(&mut deps.storage, cosmwasm_std::Decimal::percent(10)).unwrap();
+        crate::state::set_transfer_cap(
+            &mut deps.storage,
+            "cw20:cw20_contract",
+            cosmwasm_std::Decimal::percent(10),
+        )
+        .unwrap();
+
+        let contract = "cw20_contract".to_string();
+        deps.querier.update_wasm(move |query| match query {
+            cosmwasm_std::WasmQuery::Smart { contract_addr, .. } if *contract_addr == contract => {
+                cosmwasm_std::SystemResult::Ok(cosmwasm_std::ContractResult::Ok(
+                    cosmwasm_std::to_binary(&cw20::TokenInfoResponse {
+                        name: "Test Token".to_string(),
+                        symbol: "TEST".to_string(),
+                        decimals: 6,
+                        total_supply: Uint128::new(1_000_000),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => cosmwasm_std::SystemResult::Err(cosmwasm_std::SystemError::UnsupportedRequest {
+                kind: "not mocked".to_string(),
+            }),
+        });
+
+        let err = execute_transfer(
+            deps.as_mut(),
+            "channel-0".to_string(),
+            crate::amount::AmountUnchecked::cw20(200_000, "cw20_contract"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::TransferCapExceeded {});
+    }
+
+    #[test]
+    fn test_execute_transfer_checks_cap_against_post_commission_amount() {
+        // cap = 50% of a 1_000-supply token, commission = 50%: a gross send of
+        // 1000 nets a transfer_amount of exactly 500, which is at (not over) the cap
+        let mut deps = setup("admin");
+        crate::state::set_commission(&mut deps.storage, cosmwasm_std::Decimal::percent(50))
+            .unwrap();
+        crate::state::set_transfer_cap(
+            &mut deps.storage,
+            "cw20:cw20_contract",
+            cosmwasm_std::Decimal::percent(50),
+        )
+        .unwrap();
+
+        let contract = "cw20_contract".to_string();
+        deps.querier.update_wasm(move |query| match query {
+            cosmwasm_std::WasmQuery::Smart { contract_addr, .. } if *contract_addr == contract => {
+                cosmwasm_std::SystemResult::Ok(cosmwasm_std::ContractResult::Ok(
+                    cosmwasm_std::to_binary(&cw20::TokenInfoResponse {
+                        name: "Test Token".to_string(),
+                        symbol: "TEST".to_string(),
+                        decimals: 6,
+                        total_supply: Uint128::new(1_000),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => cosmwasm_std::SystemResult::Err(cosmwasm_std::SystemError::UnsupportedRequest {
+                kind: "not mocked".to_string(),
+            }),
+        });
+
+        // checking the gross 1000 against the cap would reject this; the net 500 must not
+        let res = execute_transfer(
+            deps.as_mut(),
+            "channel-0".to_string(),
+            crate::amount::AmountUnchecked::cw20(1_000, "cw20_contract"),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes
+                .iter()
+                .find(|a| a.key == "transferred")
+                .unwrap()
+                .value,
+            "500"
+        );
+    }
+
+    #[test]
+    fn test_execute_set_beneficiaries_rejects_bad_weights() {
+        let mut deps = setup("admin");
+
+        let err = execute_set_beneficiaries(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            vec![("alpha".to_string(), 4000)],
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidBeneficiaryWeights {});
+    }
+}
@@ -0,0 +1,46 @@
+use cosmwasm_std::StdError;
+use cw_controllers::AdminError;
+use std::num::TryFromIntError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
+    #[error("Amount does not fit into u64")]
+    AmountOverflow(#[from] TryFromIntError),
+
+    #[error("Cannot transfer empty amount")]
+    NoFunds {},
+
+    #[error("Insufficient funds to cover the request")]
+    InsufficientFunds {},
+
+    #[error("Commission must be between 0 and 1")]
+    InvalidCommission,
+
+    #[error("Beneficiary weights must sum to 10000 basis points")]
+    InvalidBeneficiaryWeights {},
+
+    #[error("Beneficiary address {0} is listed more than once")]
+    DuplicateBeneficiary(String),
+
+    #[error("Invalid native denom: {0}")]
+    InvalidNativeDenom(String),
+
+    #[error("cw20 denom must be of the form cw20:<address>")]
+    InvalidCw20Denom {},
+
+    #[error("Transfer cap must be a non-zero fraction of total supply, at most 1")]
+    InvalidTransferCap,
+
+    #[error("Transfer would push the channel's outstanding balance above its configured supply cap")]
+    TransferCapExceeded {},
+
+    #[error("No beneficiaries are registered to receive the distribution")]
+    NoBeneficiaries {},
+}
@@ -0,0 +1,9 @@
+pub mod amount;
+pub mod contract;
+pub mod error;
+pub mod msg;
+pub mod query;
+pub mod state;
+pub mod supply;
+
+pub use crate::error::ContractError;
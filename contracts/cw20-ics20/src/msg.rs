@@ -0,0 +1,53 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Decimal, Uint128};
+
+use crate::amount::AmountUnchecked;
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Send `amount` out over `channel_id`, after commission and the transfer cap (if any)
+    /// are applied. `amount` is validated (cw20 address checked, native denom checked)
+    /// before anything else happens.
+    Transfer {
+        channel_id: String,
+        amount: AmountUnchecked,
+    },
+    /// Set the global commission rate taken on every send, expressed as a fraction in [0, 1]
+    SetCommission { commission: Decimal },
+    /// Register the beneficiaries that split the accrued commission, weights in basis points summing to 10000
+    SetBeneficiaries { beneficiaries: Vec<(String, u16)> },
+    /// Drain the per-denom fee accumulator and pay out every registered beneficiary its weighted share
+    DistributeFees {},
+    /// Set the fallback commission rate for all sends over one channel, regardless of denom
+    SetChannelCommission {
+        channel_id: String,
+        commission: Decimal,
+    },
+    /// Set the commission rate for one denom on one channel; takes precedence over both
+    /// the channel fallback and the global default
+    SetDenomCommission {
+        channel_id: String,
+        denom: String,
+        commission: Decimal,
+    },
+    /// Set the supply-fraction cap a denom's outstanding channel balance may not exceed
+    SetTransferCap { denom: String, cap: Decimal },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// The current outstanding balance, queried total supply, and remaining headroom
+    /// under the configured transfer cap for one channel/denom pair
+    #[returns(TransferCapResponse)]
+    TransferCap { channel_id: String, denom: String },
+}
+
+#[cw_serde]
+pub struct TransferCapResponse {
+    pub outstanding: Uint128,
+    pub total_supply: Uint128,
+    /// how much more may be sent before the cap (if any) is hit; equals the uncapped
+    /// remaining supply when no cap is registered for the denom
+    pub remaining: Uint128,
+}
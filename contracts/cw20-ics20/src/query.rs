@@ -0,0 +1,40 @@
+use cosmwasm_std::{to_binary, Binary, Deps, Env, StdResult, Uint128};
+
+use crate::amount::Amount;
+use crate::msg::{QueryMsg, TransferCapResponse};
+use crate::state::{get_transfer_cap, CHANNEL_STATE};
+use crate::supply::query_total_supply;
+
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::TransferCap { channel_id, denom } => {
+            to_binary(&query_transfer_cap(deps, channel_id, denom)?)
+        }
+    }
+}
+
+fn query_transfer_cap(
+    deps: Deps,
+    channel_id: String,
+    denom: String,
+) -> StdResult<TransferCapResponse> {
+    let outstanding = CHANNEL_STATE
+        .may_load(deps.storage, (channel_id.as_str(), denom.as_str()))?
+        .unwrap_or_default()
+        .outstanding;
+
+    let amount = Amount::from_parts(denom.clone(), Uint128::zero());
+    let total_supply = query_total_supply(deps, &amount)?;
+
+    let max_outstanding = match get_transfer_cap(deps.storage, &denom)? {
+        Some(cap) => cap * total_supply,
+        None => total_supply,
+    };
+    let remaining = max_outstanding.saturating_sub(outstanding);
+
+    Ok(TransferCapResponse {
+        outstanding,
+        total_supply,
+        remaining,
+    })
+}
@@ -22,9 +22,37 @@ pub const CHANNEL_STATE: Map<(&str, &str), ChannelState> = Map::new("channel_sta
 /// Every cw20 contract we allow to be sent is stored here, possibly with a gas_limit
 pub const ALLOW_LIST: Map<&Addr, AllowInfo> = Map::new("allow_list");
 
-/// A commission fee that is taken on every send
+/// The default commission fee taken on every send, used when no more specific
+/// channel or channel+denom rate is registered
 pub const COMMISSION: Item<Decimal> = Item::new("commission");
 
+/// Per-channel fallback commission rate, used when no channel+denom override applies
+pub const CHANNEL_COMMISSION: Map<&str, Decimal> = Map::new("channel_commission");
+
+/// Per (channel_id, denom) commission override, the most specific rate `get_commission` resolves
+pub const CHANNEL_DENOM_COMMISSION: Map<(&str, &str), Decimal> = Map::new("channel_denom_commission");
+
+/// Weight (in basis points, out of `TOTAL_BENEFICIARY_WEIGHT`) that each beneficiary
+/// receives when the accrued commission is distributed
+pub const BENEFICIARIES: Map<&Addr, u16> = Map::new("beneficiaries");
+
+/// The beneficiary addresses in the order they were submitted to `set_beneficiaries`.
+/// `BENEFICIARIES.range` is ordered by address bytes, not submission order, so
+/// `DistributeFees` iterates this instead to find the one that gets the rounding
+/// remainder (the last entry the admin listed).
+pub const BENEFICIARY_ORDER: Item<Vec<Addr>> = Item::new("beneficiary_order");
+
+/// Commission collected by `calculate_lock_in` but not yet paid out, accumulated per denom
+/// (native denom, or `cw20:<address>` for cw20 tokens)
+pub const FEES_ACCRUED: Map<&str, Uint128> = Map::new("fees_accrued");
+
+/// Beneficiary weights are basis points and must sum to this value
+pub const TOTAL_BENEFICIARY_WEIGHT: u16 = 10_000;
+
+/// Per-denom cap on `ChannelState.outstanding`, expressed as a fraction of that asset's
+/// total circulating supply. Denoms with no entry here are uncapped.
+pub const TRANSFER_CAPS: Map<&str, Decimal> = Map::new("transfer_caps");
+
 #[cw_serde]
 #[derive(Default)]
 pub struct ChannelState {
@@ -36,6 +64,35 @@ pub struct ChannelState {
 pub struct Config {
     pub default_timeout: u64,
     pub default_gas_limit: Option<u64>,
+    /// sum of all registered beneficiary weights; must equal `TOTAL_BENEFICIARY_WEIGHT`
+    /// once beneficiaries have been registered
+    ///
+    /// `#[serde(default)]` so a `Config` persisted before this field existed still
+    /// deserializes, defaulting to zero (no beneficiaries registered yet)
+    #[serde(default)]
+    pub total_beneficiary_weight: u16,
+    /// how `calculate_lock_in` rounds the commission it carves out of each send
+    ///
+    /// `#[serde(default)]` so a `Config` persisted before this field existed still
+    /// deserializes, defaulting to `RoundingMode::Ceil` for backward compatibility
+    #[serde(default)]
+    pub rounding_mode: RoundingMode,
+}
+
+/// How `calculate_lock_in` rounds a fractional commission into a whole-token amount.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum RoundingMode {
+    /// always round up, in the protocol's favor (the historical behavior)
+    #[default]
+    Ceil,
+    /// always round down, in the sender's favor
+    Floor,
+    /// round half up: a fractional part of exactly 0.5 rounds up
+    Round,
+    /// round half to even: a fractional part of exactly 0.5 rounds to the nearest
+    /// even integer, otherwise behaves like `Round`
+    Bankers,
 }
 
 #[cw_serde]
@@ -60,6 +117,8 @@ pub struct ReplyArgs {
     pub amount: Uint128,
 }
 
+/// Callers on the send path should call `supply::assert_transfer_cap` first; this
+/// function does not itself enforce `TRANSFER_CAPS`.
 pub fn increase_channel_balance(
     storage: &mut dyn Storage,
     channel: &str,
@@ -113,23 +172,139 @@ pub fn undo_reduce_channel_balance(
     Ok(())
 }
 
+fn validate_commission_rate(commission: Decimal) -> Result<(), ContractError> {
+    if commission.lt(&Decimal::zero()) || commission.gt(&Decimal::one()) {
+        return Err(ContractError::InvalidCommission);
+    }
+    Ok(())
+}
+
 pub fn set_commission(
     storage: &mut dyn Storage,
     commission: Decimal
 ) -> Result<(), ContractError> {
-    if commission.lt(&Decimal::zero()) || commission.gt(&Decimal::one()) {
-        return Err(ContractError::InvalidCommission);
-    }
+    validate_commission_rate(commission)?;
     COMMISSION.save(storage, &commission)
         .map_err(|_| ContractError::Std(
             StdError::generic_err("error saving commission")
         ))
 }
 
-pub fn get_commission(storage: &dyn Storage) -> StdResult<Decimal> {
+/// Set the per-channel fallback rate used when no channel+denom override applies.
+pub fn set_channel_commission(
+    storage: &mut dyn Storage,
+    channel_id: &str,
+    commission: Decimal,
+) -> Result<(), ContractError> {
+    validate_commission_rate(commission)?;
+    CHANNEL_COMMISSION.save(storage, channel_id, &commission)?;
+    Ok(())
+}
+
+/// Set the most specific override, for one denom on one channel.
+pub fn set_channel_denom_commission(
+    storage: &mut dyn Storage,
+    channel_id: &str,
+    denom: &str,
+    commission: Decimal,
+) -> Result<(), ContractError> {
+    validate_commission_rate(commission)?;
+    CHANNEL_DENOM_COMMISSION.save(storage, (channel_id, denom), &commission)?;
+    Ok(())
+}
+
+/// Resolve the commission rate for a send, preferring the most specific rate available:
+/// channel+denom override, then per-channel fallback, then the global default.
+pub fn get_commission(storage: &dyn Storage, channel_id: &str, denom: &str) -> StdResult<Decimal> {
+    if let Some(rate) = CHANNEL_DENOM_COMMISSION.may_load(storage, (channel_id, denom))? {
+        return Ok(rate);
+    }
+    if let Some(rate) = CHANNEL_COMMISSION.may_load(storage, channel_id)? {
+        return Ok(rate);
+    }
     COMMISSION.load(storage)
 }
 
+/// Replace the full set of beneficiaries. The weights must sum to exactly
+/// `TOTAL_BENEFICIARY_WEIGHT`, which is recorded on `CONFIG` as the invariant
+/// that `DistributeFees` relies on.
+pub fn set_beneficiaries(
+    storage: &mut dyn Storage,
+    beneficiaries: Vec<(Addr, u16)>,
+) -> Result<(), ContractError> {
+    let total: u32 = beneficiaries.iter().map(|(_, weight)| *weight as u32).sum();
+    if total != TOTAL_BENEFICIARY_WEIGHT as u32 {
+        return Err(ContractError::InvalidBeneficiaryWeights {});
+    }
+
+    let mut seen = std::collections::HashSet::with_capacity(beneficiaries.len());
+    for (addr, _) in &beneficiaries {
+        if !seen.insert(addr.clone()) {
+            return Err(ContractError::DuplicateBeneficiary(addr.to_string()));
+        }
+    }
+
+    let stale: Vec<Addr> = BENEFICIARIES
+        .keys(storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for addr in stale {
+        BENEFICIARIES.remove(storage, &addr);
+    }
+    let order: Vec<Addr> = beneficiaries.iter().map(|(addr, _)| addr.clone()).collect();
+    for (addr, weight) in &beneficiaries {
+        BENEFICIARIES.save(storage, addr, weight)?;
+    }
+    BENEFICIARY_ORDER.save(storage, &order)?;
+
+    CONFIG.update(storage, |mut cfg| -> StdResult<_> {
+        cfg.total_beneficiary_weight = TOTAL_BENEFICIARY_WEIGHT;
+        Ok(cfg)
+    })?;
+    Ok(())
+}
+
+/// The registered beneficiaries with their weights, in submission order (see
+/// `BENEFICIARY_ORDER`). Returns an empty vec if none have been registered.
+pub fn get_beneficiaries(storage: &dyn Storage) -> StdResult<Vec<(Addr, u16)>> {
+    let order = BENEFICIARY_ORDER.may_load(storage)?.unwrap_or_default();
+    order
+        .into_iter()
+        .map(|addr| {
+            let weight = BENEFICIARIES.load(storage, &addr)?;
+            Ok((addr, weight))
+        })
+        .collect()
+}
+
+/// Cap must be a non-zero fraction of total supply, at most 1 (100%).
+pub fn set_transfer_cap(
+    storage: &mut dyn Storage,
+    denom: &str,
+    cap: Decimal,
+) -> Result<(), ContractError> {
+    if cap.is_zero() || cap.gt(&Decimal::one()) {
+        return Err(ContractError::InvalidTransferCap);
+    }
+    TRANSFER_CAPS.save(storage, denom, &cap)?;
+    Ok(())
+}
+
+pub fn get_transfer_cap(storage: &dyn Storage, denom: &str) -> StdResult<Option<Decimal>> {
+    TRANSFER_CAPS.may_load(storage, denom)
+}
+
+pub fn get_rounding_mode(storage: &dyn Storage) -> StdResult<RoundingMode> {
+    Ok(CONFIG.load(storage)?.rounding_mode)
+}
+
+/// Add `amount` of `denom` to the undistributed commission balance.
+pub fn accrue_fee(storage: &mut dyn Storage, denom: &str, amount: Uint128) -> StdResult<()> {
+    FEES_ACCRUED.update(storage, denom, |orig| -> StdResult<_> {
+        Ok(orig.unwrap_or_default() + amount)
+    })?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -150,4 +325,69 @@ mod tests {
 
     }
 
+    #[test]
+    fn test_get_commission_resolves_most_specific_rate() {
+        let mut deps = mock_dependencies();
+        let mut storage = deps.storage;
+
+        set_commission(&mut storage, Decimal::percent(1)).unwrap();
+        assert_eq!(
+            get_commission(&storage, "channel-0", "uusd").unwrap(),
+            Decimal::percent(1)
+        );
+
+        set_channel_commission(&mut storage, "channel-0", Decimal::percent(2)).unwrap();
+        assert_eq!(
+            get_commission(&storage, "channel-0", "uusd").unwrap(),
+            Decimal::percent(2)
+        );
+        assert_eq!(
+            get_commission(&storage, "channel-1", "uusd").unwrap(),
+            Decimal::percent(1)
+        );
+
+        set_channel_denom_commission(&mut storage, "channel-0", "uusd", Decimal::percent(3)).unwrap();
+        assert_eq!(
+            get_commission(&storage, "channel-0", "uusd").unwrap(),
+            Decimal::percent(3)
+        );
+        assert_eq!(
+            get_commission(&storage, "channel-0", "uluna").unwrap(),
+            Decimal::percent(2)
+        );
+    }
+
+    #[test]
+    fn test_set_transfer_cap_rejects_out_of_range() {
+        let mut deps = mock_dependencies();
+        let mut storage = deps.storage;
+
+        let res = set_transfer_cap(&mut storage, "uusd", Decimal::zero());
+        assert_eq!(res, Err(ContractError::InvalidTransferCap));
+
+        let res = set_transfer_cap(&mut storage, "uusd", Decimal::percent(101));
+        assert_eq!(res, Err(ContractError::InvalidTransferCap));
+
+        let res = set_transfer_cap(&mut storage, "uusd", Decimal::percent(10));
+        assert_eq!(res, Ok(()));
+        assert_eq!(
+            get_transfer_cap(&storage, "uusd").unwrap(),
+            Some(Decimal::percent(10))
+        );
+    }
+
+    #[test]
+    fn test_set_beneficiaries_rejects_duplicate_address() {
+        let mut deps = mock_dependencies();
+        let mut storage = deps.storage;
+
+        let alpha = Addr::unchecked("alpha");
+        let err = set_beneficiaries(
+            &mut storage,
+            vec![(alpha.clone(), 4000), (alpha.clone(), 6000)],
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::DuplicateBeneficiary(alpha.to_string()));
+    }
+
 }
@@ -0,0 +1,136 @@
+use cosmwasm_std::{BankQuery, Deps, QueryRequest, StdResult, SupplyResponse, Uint128};
+use cw20::{Cw20QueryMsg, TokenInfoResponse};
+
+use crate::amount::Amount;
+use crate::error::ContractError;
+use crate::state::{get_transfer_cap, set_transfer_cap, CHANNEL_STATE};
+
+/// Query the total circulating supply backing `amount`: the bank module's supply for a
+/// native coin, or the cw20 contract's own `total_supply` for a cw20 token.
+pub fn query_total_supply(deps: Deps, amount: &Amount) -> StdResult<Uint128> {
+    match amount {
+        Amount::Native(c) => {
+            let req: QueryRequest<cosmwasm_std::Empty> = QueryRequest::Bank(BankQuery::Supply {
+                denom: c.denom.clone(),
+            });
+            let res: SupplyResponse = deps.querier.query(&req)?;
+            Ok(res.amount.amount)
+        }
+        Amount::Cw20(c) => {
+            let info: TokenInfoResponse = deps
+                .querier
+                .query_wasm_smart(c.address.clone(), &Cw20QueryMsg::TokenInfo {})?;
+            Ok(info.total_supply)
+        }
+    }
+}
+
+/// Reject a send that would push a channel's outstanding balance in `amount`'s denom
+/// above its configured fraction of total supply. Call this alongside
+/// `increase_channel_balance` in the send path, before the balance is actually updated.
+/// Denoms with no registered cap are unrestricted.
+pub fn assert_transfer_cap(
+    deps: Deps,
+    channel_id: &str,
+    amount: &Amount,
+    delta: Uint128,
+) -> Result<(), ContractError> {
+    let denom = amount.denom();
+    let cap = match get_transfer_cap(deps.storage, &denom)? {
+        Some(cap) => cap,
+        None => return Ok(()),
+    };
+
+    let total_supply = query_total_supply(deps, amount)?;
+    let max_outstanding = cap * total_supply;
+
+    let current = CHANNEL_STATE
+        .may_load(deps.storage, (channel_id, denom.as_str()))?
+        .unwrap_or_default();
+    let resulting = current
+        .outstanding
+        .checked_add(delta)
+        .map_err(cosmwasm_std::StdError::from)?;
+
+    if resulting > max_outstanding {
+        return Err(ContractError::TransferCapExceeded {});
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+    use cosmwasm_std::{to_binary, ContractResult, Decimal, SystemError, SystemResult, WasmQuery};
+
+    /// Wire a cw20 `TokenInfo` response for `contract` into a fresh `mock_dependencies()`,
+    /// so `query_total_supply`/`assert_transfer_cap` can resolve a cw20 amount's supply.
+    fn deps_with_cw20_supply(
+        contract: &str,
+        total_supply: u128,
+    ) -> cosmwasm_std::OwnedDeps<
+        cosmwasm_std::testing::MockStorage,
+        cosmwasm_std::testing::MockApi,
+        cosmwasm_std::testing::MockQuerier,
+    > {
+        let mut deps = mock_dependencies();
+        let contract = contract.to_string();
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, .. } if *contract_addr == contract => {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&TokenInfoResponse {
+                        name: "Test Token".to_string(),
+                        symbol: "TEST".to_string(),
+                        decimals: 6,
+                        total_supply: Uint128::new(total_supply),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "not mocked".to_string(),
+            }),
+        });
+        deps
+    }
+
+    #[test]
+    fn test_query_total_supply_cw20() {
+        let deps = deps_with_cw20_supply("cw20_contract", 1_000_000);
+        let amount = Amount::cw20(100, "cw20_contract");
+        assert_eq!(
+            query_total_supply(deps.as_ref(), &amount).unwrap(),
+            Uint128::new(1_000_000)
+        );
+    }
+
+    #[test]
+    fn test_assert_transfer_cap_accepts_within_cap() {
+        let mut deps = deps_with_cw20_supply("cw20_contract", 1_000_000);
+        set_transfer_cap(&mut deps.storage, "cw20:cw20_contract", Decimal::percent(10)).unwrap();
+
+        let amount = Amount::cw20(50_000, "cw20_contract");
+        assert_transfer_cap(deps.as_ref(), "channel-0", &amount, Uint128::new(50_000)).unwrap();
+    }
+
+    #[test]
+    fn test_assert_transfer_cap_rejects_over_cap() {
+        let mut deps = deps_with_cw20_supply("cw20_contract", 1_000_000);
+        set_transfer_cap(&mut deps.storage, "cw20:cw20_contract", Decimal::percent(10)).unwrap();
+
+        let amount = Amount::cw20(200_000, "cw20_contract");
+        let err =
+            assert_transfer_cap(deps.as_ref(), "channel-0", &amount, Uint128::new(200_000))
+                .unwrap_err();
+        assert_eq!(err, ContractError::TransferCapExceeded {});
+    }
+
+    #[test]
+    fn test_assert_transfer_cap_is_noop_without_registered_cap() {
+        let deps = deps_with_cw20_supply("cw20_contract", 1_000_000);
+        let amount = Amount::cw20(999_999_999, "cw20_contract");
+        assert_transfer_cap(deps.as_ref(), "channel-0", &amount, Uint128::new(999_999_999))
+            .unwrap();
+    }
+}